@@ -2,6 +2,10 @@ use std::env;
 use std::fs;
 use std::io::{self, Read};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
 
 use colored::*;
 use serde::{Deserialize, Serialize};
@@ -22,6 +26,30 @@ struct CurlMetrics {
     remote_port: u16,
     local_ip: String,
     local_port: u16,
+    http_version: String,
+    scheme: String,
+    http_code: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Chart,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    fn from_env() -> Self {
+        match env::var("HTTPSTAT_FORMAT")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "json" => OutputFormat::Json,
+            "csv" => OutputFormat::Csv,
+            _ => OutputFormat::Chart,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -33,6 +61,17 @@ struct Config {
     curl_bin: String,
     debug: bool,
     timeout_secs: u64,
+    http_version: String,
+    count: u64,
+    concurrency: u64,
+    format: OutputFormat,
+    retries: u64,
+    retry_backoff_ms: u64,
+    tail_bytes: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    max_dns_ms: Option<u64>,
+    max_ttfb_ms: Option<u64>,
+    max_total_ms: Option<u64>,
 }
 
 impl Config {
@@ -58,36 +97,124 @@ impl Config {
             curl_bin: env::var("HTTPSTAT_CURL_BIN").unwrap_or_else(|_| "curl".to_string()),
             debug: getenv_bool("HTTPSTAT_DEBUG", false),
             timeout_secs: getenv_u64("HTTPSTAT_TIMEOUT", 10),
+            http_version: env::var("HTTPSTAT_HTTP_VERSION")
+                .unwrap_or_else(|_| "auto".to_string())
+                .to_lowercase(),
+            count: getenv_u64("HTTPSTAT_COUNT", 1),
+            concurrency: getenv_u64("HTTPSTAT_CONCURRENCY", 1),
+            format: OutputFormat::from_env(),
+            retries: getenv_u64("HTTPSTAT_RETRIES", 0),
+            retry_backoff_ms: getenv_u64("HTTPSTAT_RETRY_BACKOFF_MS", 200),
+            tail_bytes: env::var("HTTPSTAT_TAIL_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            connect_timeout_secs: env::var("HTTPSTAT_CONNECT_TIMEOUT")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            max_dns_ms: env::var("HTTPSTAT_MAX_DNS_MS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            max_ttfb_ms: env::var("HTTPSTAT_MAX_TTFB_MS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            max_total_ms: env::var("HTTPSTAT_MAX_TOTAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+        }
+    }
+
+    /// Curl flag that forces/negotiates the configured HTTP version, if any.
+    /// "auto" leaves the choice to curl's normal ALPN negotiation rather than
+    /// forcing QUIC, since --http3 hard-fails on curl builds without a QUIC
+    /// TLS backend (the common case on stock distro packages).
+    fn http_version_flag(&self) -> Option<&'static str> {
+        match self.http_version.as_str() {
+            "1.1" => Some("--http1.1"),
+            "2" => Some("--http2"),
+            "3" => Some("--http3"),
+            _ => None,
         }
     }
 }
 
-fn main() -> io::Result<()> {
+/// Transport/usage failure (curl error, bad args, I/O error).
+const EXIT_TRANSPORT_ERROR: i32 = 1;
+/// Request succeeded but breached a configured SLA threshold.
+const EXIT_SLA_BREACH: i32 = 3;
+
+fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 || args[1] == "--help" || args[1] == "-h" {
         print_help();
-        return Ok(());
+        return;
     }
 
-    let config = Config::from_env();
-    let url = &args[1];
-    let extra_args = &args[2..];
+    let (rest, count_override) = take_count_flag(&args[1..]);
+    if rest.is_empty() {
+        print_help();
+        return;
+    }
 
-    validate_extra_args(extra_args)?;
+    let mut config = Config::from_env();
+    if let Some(count) = count_override {
+        config.count = count;
+    }
 
-    let (_header_file, _body_file) = execute_curl(&config, url, extra_args)?;
+    let url = &rest[0];
+    let extra_args = &rest[1..];
 
-    Ok(())
+    if let Err(e) = validate_extra_args(extra_args) {
+        eprintln!("{}", e);
+        std::process::exit(EXIT_TRANSPORT_ERROR);
+    }
+
+    let result = if config.count > 1 {
+        run_benchmark(&config, url, extra_args).map(|_| false)
+    } else {
+        execute_curl(&config, url, extra_args)
+    };
+
+    match result {
+        Ok(sla_breached) => {
+            if sla_breached {
+                std::process::exit(EXIT_SLA_BREACH);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_TRANSPORT_ERROR);
+        }
+    }
+}
+
+/// Pulls a leading `-n COUNT` repeat-count flag out of the argument list,
+/// returning the remaining args (URL + curl passthrough) alongside the
+/// parsed override, if any.
+fn take_count_flag(args: &[String]) -> (Vec<String>, Option<u64>) {
+    let mut rest = args.to_vec();
+    let mut count = None;
+
+    if rest.first().is_some_and(|arg| arg == "-n") {
+        if rest.len() > 1 {
+            count = rest[1].parse().ok();
+            rest.drain(0..2);
+        } else {
+            rest.remove(0);
+        }
+    }
+
+    (rest, count)
 }
 
 fn print_help() {
     println!(
         "{}",
         r#"
-Usage: httpstat URL [CURL_OPTIONS]
+Usage: httpstat [-n COUNT] URL [CURL_OPTIONS]
 Options:
   -h, --help      Show this help
   --version       Show version
+  -n COUNT        Repeat the request COUNT times and print aggregate stats
 
 Env Options:
   HTTPSTAT_SHOW_BODY=true       Show response body
@@ -96,6 +223,26 @@ Env Options:
   HTTPSTAT_SAVE_BODY=false      Don't save body
   HTTPSTAT_CURL_BIN=/my/curl    Use custom curl
   HTTPSTAT_DEBUG=true           Enable debug log
+  HTTPSTAT_HTTP_VERSION=auto    HTTP version: 1.1, 2, 3, or auto (auto never
+                                attempts HTTP/3; it leaves negotiation to
+                                curl's default ALPN, same as omitting this var)
+  HTTPSTAT_COUNT=1              Repeat the request this many times
+  HTTPSTAT_CONCURRENCY=1        Max in-flight requests when HTTPSTAT_COUNT > 1
+  HTTPSTAT_FORMAT=chart         Output format: chart, json, or csv
+  HTTPSTAT_RETRIES=0            Retries for spurious curl failures
+  HTTPSTAT_RETRY_BACKOFF_MS=200 Base backoff between retries (exponential)
+  HTTPSTAT_TAIL_BYTES=N         Fetch/print only the last N bytes of the body
+                                (chart format with a single request only)
+  HTTPSTAT_CONNECT_TIMEOUT=N    Max seconds for the TCP connect phase
+  HTTPSTAT_MAX_DNS_MS=N         SLA: fail if DNS lookup exceeds N ms
+  HTTPSTAT_MAX_TTFB_MS=N        SLA: fail if time-to-first-byte exceeds N ms
+  HTTPSTAT_MAX_TOTAL_MS=N       SLA: fail if total time exceeds N ms
+                                (SLA thresholds are not checked when COUNT > 1)
+
+Exit codes:
+  0   Success
+  1   Transport error (curl failure, invalid arguments, I/O error)
+  3   Request succeeded but breached an SLA threshold
 "#
         .bright_blue()
     );
@@ -125,11 +272,25 @@ fn validate_extra_args(extra_args: &[String]) -> io::Result<()> {
     Ok(())
 }
 
-fn execute_curl(
+/// Runs a single request and returns whether a configured SLA threshold was
+/// breached (the transport itself having already succeeded).
+fn execute_curl(config: &Config, url: &str, extra_args: &[String]) -> io::Result<bool> {
+    if config.format == OutputFormat::Chart {
+        if let Some(tail_bytes) = config.tail_bytes {
+            return execute_tail_request(config, url, extra_args, tail_bytes);
+        }
+    }
+    let (metrics, header_file, body_file) = run_curl(config, url, extra_args)?;
+    handle_curl_output(config, &metrics, &header_file, &body_file, url)
+}
+
+/// Runs curl once and returns its raw output alongside the temp files it
+/// wrote the headers/body to. Does not inspect the exit status.
+fn spawn_curl(
     config: &Config,
     url: &str,
     extra_args: &[String],
-) -> io::Result<(NamedTempFile, NamedTempFile)> {
+) -> io::Result<(std::process::Output, NamedTempFile, NamedTempFile)> {
     let curl_format = r#"{
         "time_namelookup": %{time_namelookup},
         "time_connect": %{time_connect},
@@ -143,7 +304,10 @@ fn execute_curl(
         "remote_ip": "%{remote_ip}",
         "remote_port": %{remote_port},
         "local_ip": "%{local_ip}",
-        "local_port": %{local_port}
+        "local_port": %{local_port},
+        "http_version": "%{http_version}",
+        "scheme": "%{scheme}",
+        "http_code": %{http_code}
     }"#;
 
     let header_file = NamedTempFile::new()?;
@@ -164,38 +328,134 @@ fn execute_curl(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
+    if let Some(flag) = config.http_version_flag() {
+        cmd.arg(flag);
+    }
+
+    if let Some(connect_timeout) = config.connect_timeout_secs {
+        cmd.arg("--connect-timeout").arg(connect_timeout.to_string());
+    }
+
     if config.debug {
         println!("{} {:?}", "Executing:".bright_blue(), cmd);
     }
 
     let output = cmd.output()?;
+    Ok((output, header_file, body_file))
+}
+
+/// curl exit codes that indicate a transient, likely-retriable failure
+/// (connection reset/timeout, couldn't connect, empty reply, recv error,
+/// SSL connect error) as opposed to a permanent one (DNS resolve failure,
+/// malformed URL, certificate verification failure).
+fn is_spurious_curl_exit(code: i32) -> bool {
+    matches!(code, 7 | 28 | 52 | 56 | 35)
+}
+
+const RETRY_BACKOFF_CAP_MS: u64 = 30_000;
+
+/// Runs curl, retrying spurious failures with exponential backoff up to
+/// `config.retries` times, and parses the `-w` metrics on success.
+fn run_curl(
+    config: &Config,
+    url: &str,
+    extra_args: &[String],
+) -> io::Result<(CurlMetrics, NamedTempFile, NamedTempFile)> {
+    let attempts = config.retries + 1;
 
-    handle_curl_output(config, output, &header_file, &body_file, url)?;
-    Ok((header_file, body_file))
+    for attempt in 0..attempts {
+        let (output, header_file, body_file) = spawn_curl(config, url, extra_args)?;
+
+        if output.status.success() {
+            let stdout_str = String::from_utf8_lossy(&output.stdout);
+            let metrics: CurlMetrics = serde_json::from_str(&stdout_str).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("JSON error: {}", e))
+            })?;
+            if config.debug && attempt > 0 {
+                println!(
+                    "{} succeeded after {} attempt(s)",
+                    "Retry:".bright_blue(),
+                    attempt + 1
+                );
+            }
+            return Ok((metrics, header_file, body_file));
+        }
+
+        let exit_code = output.status.code();
+        let is_last_attempt = attempt + 1 == attempts;
+        let spurious = exit_code.is_some_and(is_spurious_curl_exit);
+
+        if !spurious || is_last_attempt {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Curl error: {}", String::from_utf8_lossy(&output.stderr)),
+            ));
+        }
+
+        // Cap the shift itself, not just the product: `1u64 << attempt`
+        // panics once attempt >= 64, which is reachable with a large enough
+        // HTTPSTAT_RETRIES. Any shift beyond 63 would be capped away below
+        // anyway, so clamping the exponent changes no observable behavior.
+        let delay_ms = config
+            .retry_backoff_ms
+            .saturating_mul(1u64 << attempt.min(63))
+            .min(RETRY_BACKOFF_CAP_MS);
+        if config.debug {
+            println!(
+                "{} attempt {} failed (exit {:?}), retrying in {}ms",
+                "Retry:".bright_blue(),
+                attempt + 1,
+                exit_code,
+                delay_ms
+            );
+        }
+        thread::sleep(Duration::from_millis(delay_ms));
+    }
+
+    unreachable!("loop always returns before exhausting attempts")
 }
 
 fn handle_curl_output(
     config: &Config,
-    output: std::process::Output,
+    metrics: &CurlMetrics,
     header_file: &NamedTempFile,
     body_file: &NamedTempFile,
     url: &str,
-) -> io::Result<()> {
-    if !output.status.success() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Curl error: {}", String::from_utf8_lossy(&output.stderr)),
-        ));
+) -> io::Result<bool> {
+    match config.format {
+        OutputFormat::Json => {
+            print_json_report(metrics, header_file, body_file, url)?;
+            Ok(evaluate_sla(config, metrics, url_is_https(url)).any())
+        }
+        OutputFormat::Csv => {
+            print_csv_report(metrics, header_file, body_file, url)?;
+            Ok(evaluate_sla(config, metrics, url_is_https(url)).any())
+        }
+        OutputFormat::Chart => print_chart_report(config, metrics, header_file, url, || {
+            handle_response_body(body_file, config.show_body, config.save_body)
+        }),
     }
+}
 
-    let stdout_str = String::from_utf8_lossy(&output.stdout);
-    let metrics: CurlMetrics = serde_json::from_str(&stdout_str)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("JSON error: {}", e)))?;
+/// Prints the connection info / headers / body / protocol / timing chart for
+/// chart-format output and returns whether an SLA threshold was breached.
+/// `print_body` is called once, between the headers and the protocol line,
+/// to print whatever body content this request wants shown.
+fn print_chart_report(
+    config: &Config,
+    metrics: &CurlMetrics,
+    header_file: &NamedTempFile,
+    url: &str,
+    print_body: impl FnOnce() -> io::Result<()>,
+) -> io::Result<bool> {
+    let https = url_is_https(url);
+    let breach = evaluate_sla(config, metrics, https);
 
-    print_connection_info(&metrics, config.show_ip);
+    print_connection_info(metrics, config.show_ip);
     print_headers(header_file)?;
-    handle_response_body(body_file, config.show_body, config.save_body)?;
-    print_timing_chart(&metrics, url_is_https(url));
+    print_body()?;
+    print_protocol_info(metrics);
+    print_timing_chart(metrics, https, &breach);
 
     if config.show_speed {
         println!(
@@ -207,9 +467,406 @@ fn handle_curl_output(
         );
     }
 
+    if breach.any() {
+        println!("{}", "SLA threshold breached".red().bold());
+    }
+
+    Ok(breach.any())
+}
+
+/// Which configured SLA thresholds, if any, a request breached.
+struct SlaBreach {
+    dns: bool,
+    ttfb: bool,
+    total: bool,
+}
+
+impl SlaBreach {
+    fn none() -> Self {
+        Self {
+            dns: false,
+            ttfb: false,
+            total: false,
+        }
+    }
+
+    fn any(&self) -> bool {
+        self.dns || self.ttfb || self.total
+    }
+}
+
+fn evaluate_sla(config: &Config, m: &CurlMetrics, https: bool) -> SlaBreach {
+    let durations = derive_phase_durations(m, https);
+    let ttfb_ms = (m.time_starttransfer * 1000.0) as u64;
+    let total_ms = (m.time_total * 1000.0) as u64;
+
+    SlaBreach {
+        dns: config.max_dns_ms.is_some_and(|max| durations.dns > max),
+        ttfb: config.max_ttfb_ms.is_some_and(|max| ttfb_ms > max),
+        total: config.max_total_ms.is_some_and(|max| total_ms > max),
+    }
+}
+
+/// Phase durations in milliseconds, derived from the raw cumulative curl
+/// timings the same way `print_timing_chart` breaks them down.
+struct PhaseDurationsMs {
+    dns: u64,
+    connect: u64,
+    ssl: u64,
+    server: u64,
+    transfer: u64,
+}
+
+fn derive_phase_durations(m: &CurlMetrics, https: bool) -> PhaseDurationsMs {
+    let dns = (m.time_namelookup * 1000.0) as u64;
+
+    // Under QUIC (HTTP/3) there is no separate TCP connect phase: the
+    // handshake is folded into the 0-RTT/1-RTT establishment covered by
+    // time_appconnect, so the usual TCP/TLS split doesn't apply. We fold
+    // that combined handshake time into `connect` and report `ssl` as 0.
+    if m.http_version == "3" {
+        let connect = (m.time_appconnect * 1000.0) as u64 - dns;
+        let server = (m.time_starttransfer * 1000.0) as u64 - dns - connect;
+        let transfer = (m.time_total * 1000.0) as u64 - dns - connect - server;
+        return PhaseDurationsMs {
+            dns,
+            connect,
+            ssl: 0,
+            server,
+            transfer,
+        };
+    }
+
+    let connect = (m.time_connect * 1000.0) as u64 - dns;
+    let ssl = if https {
+        (m.time_pretransfer * 1000.0) as u64 - dns - connect
+    } else {
+        0
+    };
+    let server = (m.time_starttransfer * 1000.0) as u64 - dns - connect - ssl;
+    let transfer = (m.time_total * 1000.0) as u64 - dns - connect - ssl - server;
+    PhaseDurationsMs {
+        dns,
+        connect,
+        ssl,
+        server,
+        transfer,
+    }
+}
+
+#[derive(Serialize)]
+struct MetricsReport<'a> {
+    metrics: &'a CurlMetrics,
+    status_code: u16,
+    dns_ms: u64,
+    connect_ms: u64,
+    ssl_ms: u64,
+    server_ms: u64,
+    transfer_ms: u64,
+    header_file: String,
+    body_file: String,
+}
+
+fn build_report<'a>(
+    metrics: &'a CurlMetrics,
+    header_file: &NamedTempFile,
+    body_file: &NamedTempFile,
+    url: &str,
+) -> MetricsReport<'a> {
+    let durations = derive_phase_durations(metrics, url_is_https(url));
+    MetricsReport {
+        metrics,
+        status_code: metrics.http_code,
+        dns_ms: durations.dns,
+        connect_ms: durations.connect,
+        ssl_ms: durations.ssl,
+        server_ms: durations.server,
+        transfer_ms: durations.transfer,
+        header_file: header_file.path().display().to_string(),
+        body_file: body_file.path().display().to_string(),
+    }
+}
+
+fn print_json_report(
+    metrics: &CurlMetrics,
+    header_file: &NamedTempFile,
+    body_file: &NamedTempFile,
+    url: &str,
+) -> io::Result<()> {
+    let report = build_report(metrics, header_file, body_file, url);
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("JSON error: {}", e)))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_csv_report(
+    metrics: &CurlMetrics,
+    header_file: &NamedTempFile,
+    body_file: &NamedTempFile,
+    url: &str,
+) -> io::Result<()> {
+    let r = build_report(metrics, header_file, body_file, url);
+    println!(
+        "time_namelookup,time_connect,time_appconnect,time_pretransfer,time_redirect,\
+         time_starttransfer,time_total,speed_download,speed_upload,remote_ip,remote_port,\
+         local_ip,local_port,http_version,scheme,status_code,dns_ms,connect_ms,ssl_ms,\
+         server_ms,transfer_ms,header_file,body_file"
+    );
+    println!(
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        r.metrics.time_namelookup,
+        r.metrics.time_connect,
+        r.metrics.time_appconnect,
+        r.metrics.time_pretransfer,
+        r.metrics.time_redirect,
+        r.metrics.time_starttransfer,
+        r.metrics.time_total,
+        r.metrics.speed_download,
+        r.metrics.speed_upload,
+        r.metrics.remote_ip,
+        r.metrics.remote_port,
+        r.metrics.local_ip,
+        r.metrics.local_port,
+        r.metrics.http_version,
+        r.metrics.scheme,
+        r.status_code,
+        r.dns_ms,
+        r.connect_ms,
+        r.ssl_ms,
+        r.server_ms,
+        r.transfer_ms,
+        r.header_file,
+        r.body_file,
+    );
     Ok(())
 }
 
+/// Runs `config.count` requests against `url` (bounded by `config.concurrency`
+/// in-flight at once) and prints an aggregated statistical summary instead of
+/// a single timing chart.
+fn run_benchmark(config: &Config, url: &str, extra_args: &[String]) -> io::Result<()> {
+    let total = config.count;
+    let workers = config.concurrency.clamp(1, total);
+
+    let next = AtomicU64::new(0);
+    // Tagged with the dispatch index (matches the index used in the
+    // "Warning: request N" message below) rather than completion order,
+    // since completion order isn't stable across runs under concurrency.
+    let results: Mutex<Vec<(u64, CurlMetrics)>> = Mutex::new(Vec::with_capacity(total as usize));
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= total {
+                    break;
+                }
+                match run_curl(config, url, extra_args) {
+                    Ok((metrics, _header_file, _body_file)) => {
+                        results.lock().unwrap().push((i, metrics));
+                    }
+                    Err(e) => eprintln!("{} request {}: {}", "Warning:".yellow(), i + 1, e),
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    if results.is_empty() {
+        return Err(io::Error::other("Curl error: all requests failed"));
+    }
+    results.sort_by_key(|(i, _)| *i);
+
+    let https = url_is_https(url);
+    match config.format {
+        OutputFormat::Json => print_benchmark_json(&results, https),
+        OutputFormat::Csv => print_benchmark_csv(&results, https),
+        OutputFormat::Chart => {
+            let metrics: Vec<CurlMetrics> = results.into_iter().map(|(_, m)| m).collect();
+            print_benchmark_summary(&metrics);
+            Ok(())
+        }
+    }
+}
+
+/// One JSON/CSV record per benchmarked run, using the same phase breakdown
+/// as the single-request `print_json_report`/`print_csv_report`.
+#[derive(Serialize)]
+struct BenchmarkRecord<'a> {
+    run: usize,
+    metrics: &'a CurlMetrics,
+    status_code: u16,
+    dns_ms: u64,
+    connect_ms: u64,
+    ssl_ms: u64,
+    server_ms: u64,
+    transfer_ms: u64,
+}
+
+fn print_benchmark_json(results: &[(u64, CurlMetrics)], https: bool) -> io::Result<()> {
+    let records: Vec<BenchmarkRecord> = results
+        .iter()
+        .map(|(i, m)| {
+            let durations = derive_phase_durations(m, https);
+            BenchmarkRecord {
+                run: *i as usize + 1,
+                metrics: m,
+                status_code: m.http_code,
+                dns_ms: durations.dns,
+                connect_ms: durations.connect,
+                ssl_ms: durations.ssl,
+                server_ms: durations.server,
+                transfer_ms: durations.transfer,
+            }
+        })
+        .collect();
+    let json = serde_json::to_string_pretty(&records)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("JSON error: {}", e)))?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn print_benchmark_csv(results: &[(u64, CurlMetrics)], https: bool) -> io::Result<()> {
+    println!(
+        "run,time_namelookup,time_connect,time_appconnect,time_pretransfer,time_redirect,\
+         time_starttransfer,time_total,speed_download,speed_upload,remote_ip,remote_port,\
+         local_ip,local_port,http_version,scheme,status_code,dns_ms,connect_ms,ssl_ms,\
+         server_ms,transfer_ms"
+    );
+    for (i, m) in results.iter() {
+        let durations = derive_phase_durations(m, https);
+        println!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            i + 1,
+            m.time_namelookup,
+            m.time_connect,
+            m.time_appconnect,
+            m.time_pretransfer,
+            m.time_redirect,
+            m.time_starttransfer,
+            m.time_total,
+            m.speed_download,
+            m.speed_upload,
+            m.remote_ip,
+            m.remote_port,
+            m.local_ip,
+            m.local_port,
+            m.http_version,
+            m.scheme,
+            m.http_code,
+            durations.dns,
+            durations.connect,
+            durations.ssl,
+            durations.server,
+            durations.transfer,
+        );
+    }
+    Ok(())
+}
+
+struct PhaseStats {
+    min: f64,
+    mean: f64,
+    median: f64,
+    p90: f64,
+    p99: f64,
+    max: f64,
+}
+
+impl PhaseStats {
+    fn from_values(mut values: Vec<f64>) -> Self {
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = values.len();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        Self {
+            min: values[0],
+            mean,
+            median: percentile(&values, 50.0),
+            p90: percentile(&values, 90.0),
+            p99: percentile(&values, 99.0),
+            max: values[n - 1],
+        }
+    }
+}
+
+/// Nearest-rank percentile over an already-sorted ascending slice.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let n = sorted.len();
+    let rank = (p / 100.0 * n as f64).ceil() as isize - 1;
+    sorted[rank.clamp(0, n as isize - 1) as usize]
+}
+
+type PhaseAccessor = fn(&CurlMetrics) -> f64;
+
+fn print_benchmark_summary(results: &[CurlMetrics]) {
+    let phases: [(&str, PhaseAccessor); 5] = [
+        ("DNS Lookup", |m| m.time_namelookup),
+        ("TCP Connection", |m| m.time_connect),
+        ("Pre Transfer", |m| m.time_pretransfer),
+        ("Start Transfer", |m| m.time_starttransfer),
+        ("Total", |m| m.time_total),
+    ];
+
+    println!(
+        "\n{} ({} requests)",
+        "Benchmark Summary".bright_blue().bold(),
+        results.len()
+    );
+    println!(
+        "{:<16}{:>9}{:>9}{:>9}{:>9}{:>9}{:>9}",
+        "Phase", "Min", "Mean", "Median", "P90", "P99", "Max"
+    );
+
+    let mut medians_ms = [0.0; 5];
+    for (i, (name, phase)) in phases.iter().enumerate() {
+        let values_ms: Vec<f64> = results.iter().map(|m| phase(m) * 1000.0).collect();
+        let stats = PhaseStats::from_values(values_ms);
+        medians_ms[i] = stats.median;
+        println!(
+            "{:<16}{:>9}{:>9}{:>9}{:>9}{:>9}{:>9}",
+            name,
+            format!("{:.1}", stats.min),
+            format!("{:.1}", stats.mean),
+            format!("{:.1}", stats.median),
+            format!("{:.1}", stats.p90),
+            format!("{:.1}", stats.p99),
+            format!("{:.1}", stats.max),
+        );
+    }
+
+    // Drive the existing single-request chart renderer off a synthetic
+    // metrics value built from the per-phase medians.
+    let sample = &results[0];
+    let median_metrics = CurlMetrics {
+        time_namelookup: medians_ms[0] / 1000.0,
+        time_connect: medians_ms[1] / 1000.0,
+        time_appconnect: PhaseStats::from_values(
+            results.iter().map(|m| m.time_appconnect).collect(),
+        )
+        .median,
+        time_pretransfer: medians_ms[2] / 1000.0,
+        time_redirect: sample.time_redirect,
+        time_starttransfer: medians_ms[3] / 1000.0,
+        time_total: medians_ms[4] / 1000.0,
+        speed_download: sample.speed_download,
+        speed_upload: sample.speed_upload,
+        remote_ip: sample.remote_ip.clone(),
+        remote_port: sample.remote_port,
+        local_ip: sample.local_ip.clone(),
+        local_port: sample.local_port,
+        http_version: sample.http_version.clone(),
+        scheme: sample.scheme.clone(),
+        http_code: sample.http_code,
+    };
+    print_protocol_info(&median_metrics);
+    print_timing_chart(
+        &median_metrics,
+        median_metrics.scheme.eq_ignore_ascii_case("https"),
+        &SlaBreach::none(),
+    );
+}
+
 fn handle_response_body(
     body_file: &NamedTempFile,
     show_body: bool,
@@ -239,6 +896,84 @@ fn handle_response_body(
     Ok(())
 }
 
+/// Looks up a header by name (case-insensitively) in a raw `-D` dump.
+fn find_header_value<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+    headers.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// Whether tail mode should issue a `Range` request rather than fetching the
+/// whole body: the server has to advertise range support, and the body has
+/// to actually be bigger than the requested tail (an unknown `Content-Length`
+/// is assumed to be worth ranging for).
+fn needs_range_request(supports_ranges: bool, content_length: Option<u64>, tail_bytes: u64) -> bool {
+    supports_ranges && content_length.is_none_or(|len| len > tail_bytes)
+}
+
+/// Serves `HTTPSTAT_TAIL_BYTES` mode. Probes the server with a `-I` (HEAD)
+/// request first so we learn `Content-Length`/`Accept-Ranges` without paying
+/// for a body download, then performs exactly one body-fetching request:
+/// a `Range` request when the server supports ranges and the body is bigger
+/// than the requested tail, or a plain GET otherwise (tailed locally).
+fn execute_tail_request(
+    config: &Config,
+    url: &str,
+    extra_args: &[String],
+    tail_bytes: u64,
+) -> io::Result<bool> {
+    let mut probe_args = extra_args.to_vec();
+    probe_args.push("-I".to_string());
+    let (_probe_metrics, probe_header_file, _probe_body_file) =
+        run_curl(config, url, &probe_args)?;
+
+    let mut probe_headers = String::new();
+    fs::File::open(probe_header_file.path())?.read_to_string(&mut probe_headers)?;
+
+    let content_length: Option<u64> = find_header_value(&probe_headers, "Content-Length")
+        .and_then(|v| v.trim().parse().ok());
+    let supports_ranges = find_header_value(&probe_headers, "Accept-Ranges")
+        .map(|v| !v.eq_ignore_ascii_case("none"))
+        .unwrap_or(false);
+
+    let need_range = needs_range_request(supports_ranges, content_length, tail_bytes);
+    let (metrics, header_file, body_file) = if need_range {
+        let mut range_args = extra_args.to_vec();
+        range_args.push("-H".to_string());
+        range_args.push(format!("Range: bytes=-{}", tail_bytes));
+        run_curl(config, url, &range_args)?
+    } else {
+        run_curl(config, url, extra_args)?
+    };
+
+    let range_honored = metrics.http_code == 206;
+    print_chart_report(config, &metrics, &header_file, url, || {
+        if range_honored {
+            // Range honored: the body file already contains only the tail.
+            print_full_body(&body_file)
+        } else {
+            print_local_tail(&body_file, tail_bytes)
+        }
+    })
+}
+
+/// Prints an entire body file, tolerating non-UTF-8 bytes.
+fn print_full_body(body_file: &NamedTempFile) -> io::Result<()> {
+    let mut buf = Vec::new();
+    fs::File::open(body_file.path())?.read_to_end(&mut buf)?;
+    println!("{}", String::from_utf8_lossy(&buf));
+    Ok(())
+}
+
+fn print_local_tail(body_file: &NamedTempFile, tail_bytes: u64) -> io::Result<()> {
+    let mut buf = Vec::new();
+    fs::File::open(body_file.path())?.read_to_end(&mut buf)?;
+    let start = buf.len().saturating_sub(tail_bytes as usize);
+    println!("{}", String::from_utf8_lossy(&buf[start..]));
+    Ok(())
+}
+
 fn url_is_https(url: &str) -> bool {
     url.starts_with("https://")
 }
@@ -273,16 +1008,65 @@ fn print_connection_info(metrics: &CurlMetrics, show_ip: bool) {
     }
 }
 
-fn print_timing_chart(m: &CurlMetrics, https: bool) {
-    let dns = (m.time_namelookup * 1000.0) as u64;
-    let connect = (m.time_connect * 1000.0) as u64 - dns;
-    let ssl = if https {
-        (m.time_pretransfer * 1000.0) as u64 - dns - connect
+fn print_protocol_info(m: &CurlMetrics) {
+    println!(
+        "{} HTTP/{} over {}",
+        "Protocol:".blue(),
+        m.http_version,
+        m.scheme
+    );
+}
+
+/// Colors a phase value red when it breached its configured SLA threshold,
+/// cyan otherwise.
+fn phase_color(text: String, breached: bool) -> ColoredString {
+    if breached {
+        text.red()
     } else {
-        0
-    };
-    let server = (m.time_starttransfer * 1000.0) as u64 - dns - connect - ssl;
-    let transfer = (m.time_total * 1000.0) as u64 - dns - connect - ssl - server;
+        text.cyan()
+    }
+}
+
+fn print_timing_chart(m: &CurlMetrics, https: bool, breach: &SlaBreach) {
+    let durations = derive_phase_durations(m, https);
+    let dns = durations.dns;
+
+    // Under QUIC (HTTP/3) there is no separate TCP connect phase: the
+    // handshake is folded into the 0-RTT/1-RTT establishment covered by
+    // time_appconnect, so the usual TCP/TLS split doesn't apply.
+    if m.http_version == "3" {
+        let quic = durations.connect;
+        let server = durations.server;
+        let transfer = durations.transfer;
+
+        println!(
+            r#"
+  DNS Lookup   QUIC Handshake   Server Processing   Content Transfer
+[{:^12}|{:^16}|{:^19}|{:^18}]
+             |                |                   |                  |
+   namelookup:{:<8}        |                   |                  |
+                     appconnect:{:<8}          |                  |
+                                      starttransfer:{:<8}          |
+                                                                 total:{:<8}"#,
+            phase_color(format!("{dns}ms"), breach.dns),
+            format!("{quic}ms").cyan(),
+            phase_color(format!("{server}ms"), breach.ttfb),
+            format!("{transfer}ms").cyan(),
+            phase_color(format!("{:.2}ms", m.time_namelookup * 1000.0), breach.dns),
+            format!("{:.2}ms", m.time_appconnect * 1000.0).cyan(),
+            phase_color(
+                format!("{:.2}ms", m.time_starttransfer * 1000.0),
+                breach.ttfb
+            ),
+            phase_color(format!("{:.2}ms", m.time_total * 1000.0), breach.total),
+        );
+        return;
+    }
+
+    let connect = durations.connect;
+    let ssl = durations.ssl;
+    let server = durations.server;
+    let transfer = durations.transfer;
 
     if https {
         println!(
@@ -295,16 +1079,19 @@ fn print_timing_chart(m: &CurlMetrics, https: bool) {
                                    pretransfer:{:<8}           |                  |
                                                      starttransfer:{:<8}          |
                                                                                 total:{:<8}"#,
-            format!("{dns}ms").cyan(),
+            phase_color(format!("{dns}ms"), breach.dns),
             format!("{connect}ms").cyan(),
             format!("{ssl}ms").cyan(),
-            format!("{server}ms").cyan(),
+            phase_color(format!("{server}ms"), breach.ttfb),
             format!("{transfer}ms").cyan(),
-            format!("{:.2}ms", m.time_namelookup * 1000.0).cyan(),
+            phase_color(format!("{:.2}ms", m.time_namelookup * 1000.0), breach.dns),
             format!("{:.2}ms", m.time_connect * 1000.0).cyan(),
             format!("{:.2}ms", m.time_pretransfer * 1000.0).cyan(),
-            format!("{:.2}ms", m.time_starttransfer * 1000.0).cyan(),
-            format!("{:.2}ms", m.time_total * 1000.0).cyan(),
+            phase_color(
+                format!("{:.2}ms", m.time_starttransfer * 1000.0),
+                breach.ttfb
+            ),
+            phase_color(format!("{:.2}ms", m.time_total * 1000.0), breach.total),
         );
     } else {
         println!(
@@ -316,15 +1103,218 @@ fn print_timing_chart(m: &CurlMetrics, https: bool) {
                         connect:{:<8}           |                  |
                                       starttransfer:{:<8}          |
                                                                  total:{:<8}"#,
-            format!("{dns}ms").cyan(),
+            phase_color(format!("{dns}ms"), breach.dns),
             format!("{connect}ms").cyan(),
-            format!("{server}ms").cyan(),
+            phase_color(format!("{server}ms"), breach.ttfb),
             format!("{transfer}ms").cyan(),
-            format!("{:.2}ms", m.time_namelookup * 1000.0).cyan(),
+            phase_color(format!("{:.2}ms", m.time_namelookup * 1000.0), breach.dns),
             format!("{:.2}ms", m.time_connect * 1000.0).cyan(),
-            format!("{:.2}ms", m.time_starttransfer * 1000.0).cyan(),
-            format!("{:.2}ms", m.time_total * 1000.0).cyan(),
+            phase_color(
+                format!("{:.2}ms", m.time_starttransfer * 1000.0),
+                breach.ttfb
+            ),
+            phase_color(format!("{:.2}ms", m.time_total * 1000.0), breach.total),
         );
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> Config {
+        Config {
+            show_body: false,
+            show_ip: true,
+            show_speed: false,
+            save_body: true,
+            curl_bin: "curl".to_string(),
+            debug: false,
+            timeout_secs: 10,
+            http_version: "auto".to_string(),
+            count: 1,
+            concurrency: 1,
+            format: OutputFormat::Chart,
+            retries: 0,
+            retry_backoff_ms: 200,
+            tail_bytes: None,
+            connect_timeout_secs: None,
+            max_dns_ms: None,
+            max_ttfb_ms: None,
+            max_total_ms: None,
+        }
+    }
+
+    fn test_metrics() -> CurlMetrics {
+        CurlMetrics {
+            time_namelookup: 0.01,
+            time_connect: 0.02,
+            time_appconnect: 0.0,
+            time_pretransfer: 0.03,
+            time_redirect: 0.0,
+            time_starttransfer: 0.1,
+            time_total: 0.2,
+            speed_download: 0.0,
+            speed_upload: 0.0,
+            remote_ip: "127.0.0.1".to_string(),
+            remote_port: 80,
+            local_ip: "127.0.0.1".to_string(),
+            local_port: 1234,
+            http_version: "1.1".to_string(),
+            scheme: "HTTP".to_string(),
+            http_code: 200,
+        }
+    }
+
+    #[test]
+    fn percentile_nearest_rank() {
+        let values: Vec<f64> = (1..=10).map(|n| n as f64).collect();
+        assert_eq!(percentile(&values, 50.0), 5.0);
+        assert_eq!(percentile(&values, 90.0), 9.0);
+        assert_eq!(percentile(&values, 99.0), 10.0);
+        assert_eq!(percentile(&values, 100.0), 10.0);
+    }
+
+    #[test]
+    fn percentile_single_value() {
+        let values = vec![42.0];
+        assert_eq!(percentile(&values, 1.0), 42.0);
+        assert_eq!(percentile(&values, 99.0), 42.0);
+    }
+
+    #[test]
+    fn spurious_curl_exit_codes() {
+        for code in [7, 28, 52, 56, 35] {
+            assert!(is_spurious_curl_exit(code), "{code} should be spurious");
+        }
+    }
+
+    #[test]
+    fn fatal_curl_exit_codes_are_not_spurious() {
+        for code in [6, 3, 60, 0, 1] {
+            assert!(!is_spurious_curl_exit(code), "{code} should not be spurious");
+        }
+    }
+
+    #[test]
+    fn sla_breach_detects_dns_threshold() {
+        let mut config = test_config();
+        config.max_dns_ms = Some(5);
+        let breach = evaluate_sla(&config, &test_metrics(), false);
+        assert!(breach.dns);
+        assert!(!breach.ttfb);
+        assert!(!breach.total);
+        assert!(breach.any());
+    }
+
+    #[test]
+    fn sla_breach_none_when_under_thresholds() {
+        let mut config = test_config();
+        config.max_dns_ms = Some(1000);
+        config.max_ttfb_ms = Some(1000);
+        config.max_total_ms = Some(1000);
+        let breach = evaluate_sla(&config, &test_metrics(), false);
+        assert!(!breach.any());
+    }
+
+    #[test]
+    fn take_count_flag_extracts_value() {
+        let args = vec!["-n".to_string(), "5".to_string(), "http://x".to_string()];
+        let (rest, count) = take_count_flag(&args);
+        assert_eq!(count, Some(5));
+        assert_eq!(rest, vec!["http://x".to_string()]);
+    }
+
+    #[test]
+    fn take_count_flag_absent_returns_none() {
+        let args = vec!["http://x".to_string(), "-v".to_string()];
+        let (rest, count) = take_count_flag(&args);
+        assert_eq!(count, None);
+        assert_eq!(rest, args);
+    }
+
+    #[test]
+    fn derive_phase_durations_splits_tcp_and_tls() {
+        let mut m = test_metrics();
+        m.time_namelookup = 0.01;
+        m.time_connect = 0.02;
+        m.time_pretransfer = 0.03;
+        m.time_starttransfer = 0.1;
+        m.time_total = 0.2;
+        let durations = derive_phase_durations(&m, true);
+        assert_eq!(durations.dns, 10);
+        assert_eq!(durations.connect, 10);
+        assert_eq!(durations.ssl, 10);
+        assert_eq!(durations.server, 70);
+        assert_eq!(durations.transfer, 100);
+    }
+
+    #[test]
+    fn derive_phase_durations_zeroes_ssl_over_plain_http() {
+        let mut m = test_metrics();
+        m.time_namelookup = 0.01;
+        m.time_connect = 0.02;
+        m.time_pretransfer = 0.03;
+        let durations = derive_phase_durations(&m, false);
+        assert_eq!(durations.ssl, 0);
+    }
+
+    #[test]
+    fn derive_phase_durations_collapses_connect_and_tls_under_quic() {
+        let mut m = test_metrics();
+        m.http_version = "3".to_string();
+        m.time_namelookup = 0.01;
+        m.time_appconnect = 0.03;
+        m.time_starttransfer = 0.1;
+        m.time_total = 0.2;
+        let durations = derive_phase_durations(&m, true);
+        // No separate TLS phase under QUIC: connect absorbs the handshake.
+        assert_eq!(durations.connect, 20);
+        assert_eq!(durations.ssl, 0);
+        assert_eq!(durations.server, 70);
+        assert_eq!(durations.transfer, 100);
+    }
+
+    #[test]
+    fn build_report_matches_derive_phase_durations() {
+        let m = test_metrics();
+        let header_file = NamedTempFile::new().unwrap();
+        let body_file = NamedTempFile::new().unwrap();
+        let report = build_report(&m, &header_file, &body_file, "http://example.com");
+        let durations = derive_phase_durations(&m, false);
+        assert_eq!(report.status_code, m.http_code);
+        assert_eq!(report.dns_ms, durations.dns);
+        assert_eq!(report.connect_ms, durations.connect);
+        assert_eq!(report.server_ms, durations.server);
+        assert_eq!(report.transfer_ms, durations.transfer);
+    }
+
+    #[test]
+    fn find_header_value_is_case_insensitive() {
+        let headers = "HTTP/1.1 200 OK\r\nContent-Length: 42\r\nAccept-Ranges: bytes\r\n";
+        assert_eq!(find_header_value(headers, "content-length"), Some("42"));
+        assert_eq!(find_header_value(headers, "ACCEPT-RANGES"), Some("bytes"));
+        assert_eq!(find_header_value(headers, "Missing"), None);
+    }
+
+    #[test]
+    fn needs_range_request_when_server_supports_ranges_and_body_is_bigger() {
+        assert!(needs_range_request(true, Some(1000), 100));
+    }
+
+    #[test]
+    fn needs_range_request_skips_range_when_body_already_fits_tail() {
+        assert!(!needs_range_request(true, Some(50), 100));
+    }
+
+    #[test]
+    fn needs_range_request_skips_range_when_server_lacks_support() {
+        assert!(!needs_range_request(false, Some(1000), 100));
+    }
+
+    #[test]
+    fn needs_range_request_assumes_worth_ranging_for_unknown_length() {
+        assert!(needs_range_request(true, None, 100));
+    }
+}
+